@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Context, Result};
+use image::DynamicImage;
+use rten::Model;
+use rten_tensor::prelude::*;
+use rten_tensor::NdTensor;
+use std::sync::LazyLock;
+use tokenizers::Tokenizer;
+
+// Embed the CLIP-style encoders directly into the binary, the same way the
+// detection/recognition models are embedded for OCR.
+static IMAGE_ENCODER_DATA: &[u8] = include_bytes!("../models/clip-image-encoder.rten");
+static TEXT_ENCODER_DATA: &[u8] = include_bytes!("../models/clip-text-encoder.rten");
+// The model's own BPE vocabulary/merges, so text tokens land on the same
+// embedding rows the encoder was trained with.
+static TOKENIZER_DATA: &[u8] = include_bytes!("../models/clip-tokenizer.json");
+
+/// CLIP's fixed context length and padding token id.
+const CONTEXT_LEN: usize = 77;
+const PAD_TOKEN: i32 = 0;
+
+// Lazily load the BPE tokenizer paired with the text encoder.
+static TOKENIZER: LazyLock<Result<Tokenizer>> = LazyLock::new(|| {
+    Tokenizer::from_bytes(TOKENIZER_DATA)
+        .map_err(|e| anyhow!("Failed to load embedded CLIP tokenizer: {e}"))
+});
+
+/// The square input size and normalization constants used by CLIP preprocessing.
+const INPUT_SIZE: usize = 224;
+const MEAN: [f32; 3] = [0.481_454_6, 0.457_827_5, 0.408_210_7];
+const STD: [f32; 3] = [0.268_629_55, 0.261_302_6, 0.275_777_1];
+
+/// The paired image and text encoders that share an embedding space.
+struct ClipModels {
+    image: Model,
+    text: Model,
+}
+
+// Lazily initialize the encoders.
+//
+// Like the OCR engine, this must never panic: embeddings are an optional
+// capability and load failures should surface as ordinary CLI errors.
+static CLIP: LazyLock<Result<ClipModels>> = LazyLock::new(|| {
+    let image = Model::load(IMAGE_ENCODER_DATA.to_vec())
+        .context("Failed to load embedded CLIP image encoder")?;
+    let text = Model::load(TEXT_ENCODER_DATA.to_vec())
+        .context("Failed to load embedded CLIP text encoder")?;
+    Ok(ClipModels { image, text })
+});
+
+fn models() -> Result<&'static ClipModels> {
+    CLIP.as_ref()
+        .map_err(|e| anyhow!("CLIP model initialization failed: {e}"))
+}
+
+/// Encode an image into an L2-normalized embedding vector.
+pub fn embed_image(image: &DynamicImage) -> Result<Vec<f32>> {
+    let input = preprocess_image(image);
+    let output = models()?
+        .image
+        .run_one(input.view().into(), None)
+        .context("Failed to run CLIP image encoder")?;
+    finish(output)
+}
+
+/// Encode a text query into an L2-normalized embedding vector in the same space.
+pub fn embed_text(text: &str) -> Result<Vec<f32>> {
+    let tokens = tokenize(text)?;
+    let input = NdTensor::from_data([1, tokens.len()], tokens);
+    let output = models()?
+        .text
+        .run_one(input.view().into(), None)
+        .context("Failed to run CLIP text encoder")?;
+    finish(output)
+}
+
+/// Cosine similarity of two L2-normalized vectors (ie their dot product).
+pub fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Serialize an embedding to a little-endian byte blob for storage.
+pub fn to_blob(vector: &[f32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(vector.len() * 4);
+    for value in vector {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+/// Parse an embedding blob produced by [`to_blob`].
+pub fn from_blob(bytes: &[u8]) -> Result<Vec<f32>> {
+    if bytes.len() % 4 != 0 {
+        return Err(anyhow!("embedding blob length is not a multiple of 4"));
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect())
+}
+
+/// Resize to the encoder's square input and normalize into an NCHW tensor.
+fn preprocess_image(image: &DynamicImage) -> NdTensor<f32, 4> {
+    let resized = image
+        .resize_exact(
+            INPUT_SIZE as u32,
+            INPUT_SIZE as u32,
+            image::imageops::FilterType::CatmullRom,
+        )
+        .to_rgb8();
+
+    let mut tensor = NdTensor::zeros([1, 3, INPUT_SIZE, INPUT_SIZE]);
+    for (x, y, pixel) in resized.enumerate_pixels() {
+        let (x, y) = (x as usize, y as usize);
+        for c in 0..3 {
+            let value = pixel[c] as f32 / 255.0;
+            tensor[[0, c, y, x]] = (value - MEAN[c]) / STD[c];
+        }
+    }
+    tensor
+}
+
+/// Extract an encoder's f32 output and L2-normalize it into an embedding.
+fn finish(output: rten::Output) -> Result<Vec<f32>> {
+    let tensor: rten_tensor::Tensor<f32> =
+        output.try_into().context("Unexpected CLIP encoder output")?;
+    let mut vector: Vec<f32> = tensor.iter().copied().collect();
+    normalize(&mut vector);
+    Ok(vector)
+}
+
+/// L2-normalize in place so similarity reduces to a dot product.
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+/// Tokenize a query for the text encoder using the model's BPE vocabulary.
+///
+/// The tokenizer supplies the byte-pair ids and the start/end-of-text markers;
+/// the result is truncated or zero-padded to CLIP's fixed context length.
+fn tokenize(text: &str) -> Result<Vec<i32>> {
+    let tokenizer = TOKENIZER
+        .as_ref()
+        .map_err(|e| anyhow!("CLIP tokenizer initialization failed: {e}"))?;
+    let encoding = tokenizer
+        .encode(text, true)
+        .map_err(|e| anyhow!("Failed to tokenize query: {e}"))?;
+
+    let mut tokens: Vec<i32> = encoding
+        .get_ids()
+        .iter()
+        .take(CONTEXT_LEN)
+        .map(|&id| id as i32)
+        .collect();
+    tokens.resize(CONTEXT_LEN, PAD_TOKEN);
+    Ok(tokens)
+}
@@ -1,11 +1,43 @@
+mod embed;
+mod ocr;
+
 use anyhow::{Context, Result};
-use clap::Parser;
-use image::{DynamicImage, ImageFormat, ImageReader};
-use leptess::{LepTess, Variable};
+use clap::{Parser, ValueEnum};
+use ignore::WalkBuilder;
+use image::ImageReader;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::new_debouncer;
+use ocr::{OcrBackend, OcrsBackend, TesseractBackend};
+use rayon::prelude::*;
 use rusqlite::Connection;
-use std::fs;
-use std::path::Path;
-use tempfile::Builder;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// The outcome of OCRing a single image, sent from a worker to the writer thread.
+struct OcrOutcome {
+    path: PathBuf,
+    /// The recognized text, or `None` if decoding/OCR failed or panicked.
+    text: Option<String>,
+    /// The engine that produced the result, recorded in `ocr_engine`.
+    engine: &'static str,
+    /// The language set used, recorded in `ocr_lang`.
+    lang: String,
+    /// Positional segments, when the engine reports geometry.
+    segments: Vec<ocr::OcrSegment>,
+    /// An L2-normalized image embedding, when semantic indexing is enabled.
+    embedding: Option<Vec<f32>>,
+}
+
+/// The OCR engine to run.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Engine {
+    /// The embedded ocrs/rten engine.
+    Ocrs,
+    /// The Tesseract/leptess engine.
+    Tesseract,
+}
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -21,20 +53,94 @@ struct Cli {
     /// Enable debug output
     #[arg(short, long)]
     debug: bool,
+
+    /// Maximum directory depth to descend into (1 = only the top level)
+    #[arg(long, value_name = "DEPTH")]
+    max_depth: Option<usize>,
+
+    /// Do not honor `.gitignore`/`.ignore` files while crawling
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Include hidden files and directories while crawling
+    #[arg(long)]
+    hidden: bool,
+
+    /// Which OCR engine to use
+    #[arg(long, value_enum, default_value_t = Engine::Tesseract)]
+    engine: Engine,
+
+    /// Tesseract language set, `+`-joined (eg `eng`, `eng+deu+jpn`)
+    #[arg(long, default_value = "eng")]
+    lang: String,
+
+    /// Tesseract page-segmentation mode
+    #[arg(long, default_value_t = 1)]
+    psm: u8,
+
+    /// After the initial crawl, keep running and OCR new or modified files
+    #[arg(long)]
+    watch: bool,
+
+    /// Also compute CLIP-style image embeddings, and search them for the query
+    #[arg(long)]
+    semantic: bool,
+
+    /// Number of results to return for a semantic search
+    #[arg(long, default_value_t = 10)]
+    top_k: usize,
+}
+
+impl Cli {
+    /// Build the OCR backend selected on the command line.
+    fn backend(&self) -> Box<dyn OcrBackend> {
+        match self.engine {
+            Engine::Ocrs => Box::new(OcrsBackend),
+            Engine::Tesseract => {
+                Box::new(TesseractBackend::new(self.lang.clone(), self.psm, self.debug))
+            }
+        }
+    }
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     let db_path = Path::new(&cli.directory).join(".ocr_results.db");
-    search_and_ocr_photos(&cli.directory, cli.debug, &db_path)
+    let backend = cli.backend();
+    backend
+        .validate()
+        .context("OCR engine is not usable with the requested configuration")?;
+    search_and_ocr_photos(&cli.directory, backend.as_ref(), &db_path, &cli)
         .context("Error during search and OCR")?;
 
+    if cli.watch {
+        watch_and_ocr(&cli.directory, backend.as_ref(), &db_path, cli.semantic)
+            .context("Error while watching for changes")?;
+    }
+
     if let Some(search_text) = cli.search_text {
+        // Semantic search ranks images by embedding similarity; it falls back to
+        // text search when no embeddings have been indexed yet.
+        if cli.semantic {
+            let ranked = semantic_search(&db_path, &search_text, cli.top_k)
+                .context("Error during semantic search")?;
+            if !ranked.is_empty() {
+                for (path, score) in ranked {
+                    println!("{path}  ({score:.3})");
+                }
+                return Ok(());
+            }
+            eprintln!("No embeddings indexed; falling back to text search.");
+        }
+
         let results =
             search_ocr_results(&db_path, &search_text).context("Error searching OCR results")?;
-        for (filename, _) in results {
-            println!("{}", filename);
+        for (filename, snippet) in results {
+            println!("{filename}");
+            if !snippet.is_empty() {
+                println!("    {snippet}");
+            }
         }
     }
 
@@ -45,125 +151,465 @@ fn init_db(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS ocr_results (
             filename TEXT PRIMARY KEY,
-            text TEXT NOT NULL
+            text TEXT NOT NULL,
+            ocr_success BOOLEAN NOT NULL DEFAULT 1,
+            ocr_engine TEXT NOT NULL DEFAULT 'tesseract',
+            ocr_lang TEXT NOT NULL DEFAULT ''
         )",
         [],
     )
     .context("Failed to create table")?;
-    Ok(())
-}
 
-fn process_image(
-    ocr: &mut LepTess,
-    conn: &Connection,
-    path: &Path,
-    image: Option<DynamicImage>,
-) -> Result<()> {
-    let temp_file = Builder::new()
-        .suffix(".png")
-        .tempfile()
-        .context("Failed to create temporary file")?;
-    let temp_path = temp_file.path();
-
-    if let Some(img) = image {
-        img.save_with_format(temp_path, ImageFormat::Png)
-            .context("Failed to save image to temporary file")?;
-    } else {
-        fs::copy(path, temp_path).context("Failed to copy image to temporary file")?;
-    }
+    // Migrate databases created against the original `filename, text` schema by
+    // adding the columns later requests introduced. CREATE TABLE IF NOT EXISTS
+    // leaves a pre-existing table untouched, so the ADD COLUMNs are what keep old
+    // stores usable rather than aborting mid-run with "no such column".
+    ensure_column(conn, "ocr_results", "ocr_success", "BOOLEAN NOT NULL DEFAULT 1")?;
+    ensure_column(conn, "ocr_results", "ocr_engine", "TEXT NOT NULL DEFAULT 'tesseract'")?;
+    ensure_column(conn, "ocr_results", "ocr_lang", "TEXT NOT NULL DEFAULT ''")?;
 
-    ocr.set_image(temp_path)
-        .context("Failed to set image for OCR")?;
+    // Mirror the `text` column into an FTS5 index so searches can rank by
+    // relevance and answer phrase/prefix/NEAR queries. `filename` rides along
+    // UNINDEXED purely so snippets can be attributed back to a file.
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS ocr_fts USING fts5(
+            filename UNINDEXED,
+            text,
+            tokenize = 'unicode61'
+        );
 
-    let text = ocr.get_utf8_text().context("Failed to get OCR text")?;
+        CREATE TRIGGER IF NOT EXISTS ocr_results_ai AFTER INSERT ON ocr_results BEGIN
+            INSERT INTO ocr_fts(filename, text) VALUES (new.filename, new.text);
+        END;
 
-    let trimmed_text = text.trim();
-    if !trimmed_text.is_empty() {
-        store_ocr_result(conn, path, trimmed_text).context("Failed to store OCR result")?;
-    } else {
-        println!("No text found in the image.");
-    }
+        CREATE TRIGGER IF NOT EXISTS ocr_results_ad AFTER DELETE ON ocr_results BEGIN
+            DELETE FROM ocr_fts WHERE filename = old.filename;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS ocr_results_au AFTER UPDATE ON ocr_results BEGIN
+            UPDATE ocr_fts SET text = new.text WHERE filename = new.filename;
+        END;",
+    )
+    .context("Failed to create FTS index")?;
+
+    // Positional segments recovered from the ocrs engine: each line's text plus
+    // its bounding box (stored as JSON) in image pixel coordinates. This enables
+    // highlight overlays and "where on the page" queries that the flattened text
+    // alone cannot answer.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ocr_segments (
+            filename TEXT NOT NULL,
+            path TEXT NOT NULL,
+            line_index INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            bbox TEXT NOT NULL,
+            PRIMARY KEY (path, line_index)
+        )",
+        [],
+    )
+    .context("Failed to create table ocr_segments")?;
+
+    // L2-normalized CLIP-style image embeddings, one per path, for semantic
+    // search. Stored as a raw little-endian f32 BLOB and scanned brute-force.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS embeddings (
+            path TEXT PRIMARY KEY,
+            vector BLOB NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create table embeddings")?;
 
+    // Backfill the index from any rows that predate it (eg databases created
+    // before the FTS mirror existed). Rows written by store_ocr_result's upsert
+    // fire the triggers, so only genuinely missing rows need seeding here.
+    conn.execute(
+        "INSERT INTO ocr_fts(filename, text)
+         SELECT filename, text FROM ocr_results
+         WHERE filename NOT IN (SELECT filename FROM ocr_fts)",
+        [],
+    )
+    .context("Failed to backfill FTS index")?;
+
+    Ok(())
+}
+
+/// Add `column` to `table` if it is not already present, using `PRAGMA
+/// table_info` introspection so the migration is idempotent across runs.
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({table})"))
+        .context("Failed to inspect table schema")?;
+    let existing: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .context("Failed to read table schema")?
+        .collect::<Result<_, _>>()
+        .context("Failed to collect table schema")?;
+
+    if !existing.iter().any(|name| name == column) {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+            [],
+        )
+        .with_context(|| format!("Failed to add column {column} to {table}"))?;
+    }
     Ok(())
 }
 
-fn search_and_ocr_photos(directory: &str, debug: bool, db_path: &Path) -> Result<()> {
+fn search_and_ocr_photos(
+    directory: &str,
+    backend: &dyn OcrBackend,
+    db_path: &Path,
+    cli: &Cli,
+) -> Result<()> {
     let path = Path::new(directory);
-    let mut ocr = LepTess::new(None, "eng").context("Failed to initialize LepTess")?;
-    ocr.set_variable(Variable::TesseditPagesegMode, "1")
-        .context("Failed to set TesseditPagesegMode")?;
 
-    if !debug {
-        ocr.set_variable(Variable::DebugFile, "/dev/null")
-            .context("Failed to set DebugFile")?;
+    let conn = Connection::open(db_path).context("Failed to open database connection")?;
+    init_db(&conn)?;
+
+    if !path.is_dir() {
+        return Ok(());
     }
 
+    let engine = backend.name();
+    let lang = backend.language().to_string();
+
+    // Collect the files that still need OCR up front, so the reads against the
+    // connection happen before it is handed to the writer thread below. A file
+    // is reprocessed if it was last OCRed by a different engine or language set.
+    let files = collect_pending_files(&conn, path, cli, engine, &lang)?;
+
+    // OCR is CPU-bound and embarrassingly parallel: fan the decode/recognize work
+    // out across the rayon pool and funnel every outcome through a channel into a
+    // single writer thread that owns the (non-Sync) connection.
+    let (tx, rx) = mpsc::channel::<OcrOutcome>();
+    let writer = std::thread::spawn(move || -> Result<()> {
+        for outcome in rx {
+            write_outcome(&conn, &outcome)?;
+        }
+        Ok(())
+    });
+
+    files.par_iter().for_each_with(tx, |tx, file| {
+        println!("Processing file: {}", file.display());
+        let (text, segments, embedding) = recognize_file(backend, file, cli.semantic);
+        let _ = tx.send(OcrOutcome {
+            path: file.clone(),
+            text,
+            engine,
+            lang: lang.clone(),
+            segments,
+            embedding,
+        });
+    });
+
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("OCR writer thread panicked"))?
+}
+
+/// Watch `directory` and OCR images as they are created or modified, streaming
+/// results into the same store so concurrent searches see an up-to-date index.
+fn watch_and_ocr(
+    directory: &str,
+    backend: &dyn OcrBackend,
+    db_path: &Path,
+    semantic: bool,
+) -> Result<()> {
     let conn = Connection::open(db_path).context("Failed to open database connection")?;
     init_db(&conn)?;
 
-    if path.is_dir() {
-        for entry in fs::read_dir(path).context("Failed to read directory")? {
-            let entry = entry.context("Failed to read directory entry")?;
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if let Some(ext_str) = extension.to_str() {
-                        let ext_lower = ext_str.to_lowercase();
-                        if ["jpg", "jpeg", "png", "gif", "bmp", "tiff"]
-                            .contains(&ext_lower.as_str())
-                        {
-                            if !file_exists_in_db(&conn, &path)
-                                .context("Failed to check if file exists in database")?
-                            {
-                                println!("Processing file: {}", path.display());
-                                process_image(&mut ocr, &conn, &path, None)?;
-                            }
-                        } else if ["webp", "heic", "heif", "avif", "jxl"]
-                            .contains(&ext_lower.as_str())
-                        {
-                            if !file_exists_in_db(&conn, &path)
-                                .context("Failed to check if file exists in database")?
-                            {
-                                println!("Processing file: {}", path.display());
-                                let image = ImageReader::open(&path)
-                                    .context("Failed to open image")?
-                                    .decode()
-                                    .context("Failed to decode image")?;
-                                process_image(&mut ocr, &conn, &path, Some(image))?;
-                            }
-                        }
-                    }
-                }
+    let engine = backend.name();
+    let lang = backend.language().to_string();
+
+    // The debouncer coalesces rapid bursts (eg an editor writing a file in
+    // several chunks) into a single event per path over the debounce window.
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(Duration::from_millis(500), tx)
+        .context("Failed to create filesystem watcher")?;
+    debouncer
+        .watcher()
+        .watch(Path::new(directory), RecursiveMode::Recursive)
+        .context("Failed to watch directory")?;
+
+    println!("Watching {directory} for new images (press Ctrl-C to stop)...");
+
+    for result in rx {
+        let events = result.map_err(|errs| anyhow::anyhow!("Filesystem watch error: {errs:?}"))?;
+        for event in events {
+            let path = event.path;
+            if !path.is_file() || !is_supported_image(&path) {
+                continue;
             }
+            // A watch event means the file was just created or modified, so
+            // always re-OCR it rather than consulting file_exists_in_db (which
+            // would skip a re-saved image whose content changed). The initial
+            // crawl already handled pre-existing, unchanged files, and the
+            // store's UPSERT keeps a single up-to-date row per path.
+
+            println!("Processing file: {}", path.display());
+            let (text, segments, embedding) = recognize_file(backend, &path, semantic);
+            let outcome = OcrOutcome {
+                path,
+                text,
+                engine,
+                lang: lang.clone(),
+                segments,
+                embedding,
+            };
+            write_outcome(&conn, &outcome)?;
         }
     }
 
     Ok(())
 }
 
-fn file_exists_in_db(conn: &Connection, path: &Path) -> Result<bool> {
+/// Decode and OCR a single file, isolating panics so one bad input can't abort
+/// the caller. Returns `None` text on any failure.
+fn recognize_file(
+    backend: &dyn OcrBackend,
+    path: &Path,
+    semantic: bool,
+) -> (Option<String>, Vec<ocr::OcrSegment>, Option<Vec<f32>>) {
+    // A malformed image can make native image/Tesseract code panic or abort;
+    // isolate each file so one bad input cannot kill the run.
+    let recognized = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let image = ImageReader::open(path)
+            .context("Failed to open image")?
+            .decode()
+            .context("Failed to decode image")?;
+        let (text, segments) = backend.recognize_with_segments(&image)?;
+        // Reuse the already-decoded frame to embed, avoiding a second decode.
+        let embedding = if semantic {
+            Some(embed::embed_image(&image)?)
+        } else {
+            None
+        };
+        Ok::<_, anyhow::Error>((text, segments, embedding))
+    }));
+
+    match recognized {
+        Ok(Ok((text, segments, embedding))) => (Some(text), segments, embedding),
+        Ok(Err(err)) => {
+            eprintln!("OCR failed for {}: {err:#}", path.display());
+            (None, Vec::new(), None)
+        }
+        Err(_) => {
+            eprintln!("OCR panicked for {}", path.display());
+            (None, Vec::new(), None)
+        }
+    }
+}
+
+/// Persist a single OCR outcome (flat text plus any positional segments).
+fn write_outcome(conn: &Connection, outcome: &OcrOutcome) -> Result<()> {
+    let success = outcome.text.is_some();
+    let text = outcome.text.as_deref().unwrap_or_default();
+    store_ocr_result(
+        conn,
+        &outcome.path,
+        text.trim(),
+        success,
+        outcome.engine,
+        &outcome.lang,
+    )
+    .context("Failed to store OCR result")?;
+    if !outcome.segments.is_empty() {
+        store_ocr_segments(conn, &outcome.path, &outcome.segments)
+            .context("Failed to store OCR segments")?;
+    }
+    if let Some(embedding) = &outcome.embedding {
+        store_embedding(conn, &outcome.path, embedding)
+            .context("Failed to store embedding")?;
+    }
+    Ok(())
+}
+
+/// Walk `root` and return the supported image files that still need OCR by
+/// `engine` (never processed, or last processed by a different engine or
+/// language set).
+fn collect_pending_files(
+    conn: &Connection,
+    root: &Path,
+    cli: &Cli,
+    engine: &str,
+    lang: &str,
+) -> Result<Vec<PathBuf>> {
+    // Descend recursively, honoring ignore files and hidden-file rules unless the
+    // user opted out. `ignore` yields top-level files at depth 1, so the
+    // user-facing value maps through directly (1 = only the top level).
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(!cli.no_ignore)
+        .hidden(!cli.hidden)
+        .max_depth(cli.max_depth);
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        let entry = entry.context("Failed to read directory entry")?;
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+
+        if !is_supported_image(path) {
+            continue;
+        }
+
+        if !file_exists_in_db(conn, path, engine, lang)
+            .context("Failed to check if file exists in database")?
+        {
+            files.push(path.to_path_buf());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Whether `path` has an extension recall knows how to OCR.
+fn is_supported_image(path: &Path) -> bool {
+    let Some(ext) = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+    else {
+        return false;
+    };
+    [
+        "jpg", "jpeg", "png", "gif", "bmp", "tiff", "webp", "heic", "heif", "avif", "jxl",
+    ]
+    .contains(&ext.as_str())
+}
+
+/// Whether `path` has already been OCRed by `engine` with this `lang` set. A row
+/// produced by a different engine or language is treated as absent, so
+/// re-running with `--engine`/`--lang` upgrades those results.
+fn file_exists_in_db(conn: &Connection, path: &Path, engine: &str, lang: &str) -> Result<bool> {
     let mut stmt = conn
-        .prepare("SELECT 1 FROM ocr_results WHERE filename = ?1 LIMIT 1")
+        .prepare(
+            "SELECT 1 FROM ocr_results
+             WHERE filename = ?1 AND ocr_engine = ?2 AND ocr_lang = ?3 LIMIT 1",
+        )
         .context("Failed to prepare statement")?;
     let exists = stmt
-        .exists([path.to_string_lossy().to_string()])
+        .exists(rusqlite::params![
+            path.to_string_lossy().to_string(),
+            engine,
+            lang
+        ])
         .context("Failed to check if file exists in database")?;
     Ok(exists)
 }
 
-fn store_ocr_result(conn: &Connection, path: &Path, text: &str) -> Result<()> {
+fn store_ocr_result(
+    conn: &Connection,
+    path: &Path,
+    text: &str,
+    success: bool,
+    engine: &str,
+    lang: &str,
+) -> Result<()> {
+    // UPSERT rather than INSERT OR REPLACE: REPLACE deletes then re-inserts the
+    // row, but with recursive_triggers off the implicit delete skips the FTS
+    // delete trigger, leaving stale/duplicate rows in `ocr_fts`. ON CONFLICT
+    // fires the AFTER UPDATE trigger, keeping the mirror exactly in sync.
     conn.execute(
-        "INSERT OR REPLACE INTO ocr_results (filename, text) VALUES (?1, ?2)",
-        [path.to_string_lossy().to_string(), text.to_string()],
+        "INSERT INTO ocr_results (filename, text, ocr_success, ocr_engine, ocr_lang)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(filename) DO UPDATE SET
+             text = excluded.text,
+             ocr_success = excluded.ocr_success,
+             ocr_engine = excluded.ocr_engine,
+             ocr_lang = excluded.ocr_lang",
+        rusqlite::params![path.to_string_lossy().to_string(), text, success, engine, lang],
     )
     .context("Failed to store OCR result")?;
     Ok(())
 }
 
+/// Persist the positional segments for an image, replacing any previous ones.
+fn store_ocr_segments(conn: &Connection, path: &Path, segments: &[ocr::OcrSegment]) -> Result<()> {
+    let path_str = path.to_string_lossy().to_string();
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path_str.clone());
+
+    conn.execute("DELETE FROM ocr_segments WHERE path = ?1", [&path_str])
+        .context("Failed to clear existing segments")?;
+
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO ocr_segments (filename, path, line_index, text, bbox)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .context("Failed to prepare segment insert")?;
+    for (line_index, segment) in segments.iter().enumerate() {
+        let bbox = serde_json::to_string(&segment.bbox).context("Failed to serialize bbox")?;
+        stmt.execute(rusqlite::params![
+            filename,
+            path_str,
+            line_index as i64,
+            segment.text,
+            bbox,
+        ])
+        .context("Failed to store OCR segment")?;
+    }
+    Ok(())
+}
+
+/// Persist an image embedding, replacing any previous vector for the same path.
+fn store_embedding(conn: &Connection, path: &Path, vector: &[f32]) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO embeddings (path, vector) VALUES (?1, ?2)",
+        rusqlite::params![path.to_string_lossy().to_string(), embed::to_blob(vector)],
+    )
+    .context("Failed to store embedding")?;
+    Ok(())
+}
+
+/// Encode `query` and return the `top_k` paths most similar by cosine distance.
+fn semantic_search(db_path: &Path, query: &str, top_k: usize) -> Result<Vec<(String, f32)>> {
+    let query_vec = embed::embed_text(query).context("Failed to embed query")?;
+
+    let conn = Connection::open(db_path).context("Failed to open database connection")?;
+    let mut stmt = conn
+        .prepare("SELECT path, vector FROM embeddings")
+        .context("Failed to prepare statement")?;
+    let rows = stmt
+        .query_map([], |row| {
+            let path: String = row.get(0)?;
+            let blob: Vec<u8> = row.get(1)?;
+            Ok((path, blob))
+        })
+        .context("Failed to execute query")?;
+
+    // Brute-force scan: adequate for tens of thousands of images.
+    let mut scored = Vec::new();
+    for row in rows {
+        let (path, blob) = row.context("Failed to read embedding row")?;
+        let vector = embed::from_blob(&blob)
+            .with_context(|| format!("Corrupt embedding for {path}"))?;
+        scored.push((path, embed::similarity(&query_vec, &vector)));
+    }
+
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(top_k);
+    Ok(scored)
+}
+
 fn search_ocr_results(db_path: &Path, search_text: &str) -> Result<Vec<(String, String)>> {
     let conn = Connection::open(db_path).context("Failed to open database connection")?;
+    // `search_text` is passed straight to FTS5, so the full MATCH syntax is
+    // available: phrases ("annual report"), NEAR(a b, 3), and prefix queries
+    // (invo*). Results come back best-match-first with a snippet of context.
     let mut stmt = conn
-        .prepare("SELECT filename, text FROM ocr_results WHERE text LIKE '%' || ?1 || '%'")
+        .prepare(
+            "SELECT filename, snippet(ocr_fts, 1, '[', ']', '…', 10)
+             FROM ocr_fts
+             WHERE ocr_fts MATCH ?1
+             ORDER BY bm25(ocr_fts)",
+        )
         .context("Failed to prepare statement")?;
     let results = stmt
         .query_map([search_text], |row| Ok((row.get(0)?, row.get(1)?)))
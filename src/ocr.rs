@@ -1,8 +1,29 @@
 use anyhow::{anyhow, Context, Result};
-use image::DynamicImage;
+use image::{DynamicImage, ImageFormat};
+use leptess::{LepTess, Variable};
 use ocrs::{ImageSource, OcrEngine, OcrEngineParams};
 use rten::Model;
+use rten_imageproc::BoundingRect;
+use serde::Serialize;
+use std::cell::RefCell;
 use std::sync::LazyLock;
+use tempfile::Builder;
+
+/// A bounding box in image pixel coordinates.
+#[derive(Debug, Clone, Serialize)]
+pub struct BBox {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+/// One recognized text line together with where it sits on the page.
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrSegment {
+    pub text: String,
+    pub bbox: BBox,
+}
 
 // Embed models directly into the binary at compile time
 static DETECTION_MODEL_DATA: &[u8] = include_bytes!("../models/text-detection.rten");
@@ -26,8 +47,149 @@ static OCR_ENGINE: LazyLock<Result<OcrEngine>> = LazyLock::new(|| {
     .context("Failed to initialize OCR engine")
 });
 
-/// Perform OCR on an image and return the extracted text.
-pub fn extract_text(image: &DynamicImage) -> Result<String> {
+/// A pluggable OCR engine. Implementors must be shareable across the rayon pool;
+/// engines that are not themselves `Sync` (eg Tesseract) keep their state
+/// thread-local so the backend value itself carries only configuration.
+pub trait OcrBackend: Send + Sync {
+    /// Recognize the text in `img` and return it as a single flattened string.
+    fn recognize(&self, img: &DynamicImage) -> Result<String>;
+
+    /// Recognize positional segments. Backends without geometry return none.
+    fn segments(&self, _img: &DynamicImage) -> Result<Vec<OcrSegment>> {
+        Ok(Vec::new())
+    }
+
+    /// Recognize flat text and positional segments in a single pass, so callers
+    /// that want both do not run the engine twice. The default combines the two
+    /// methods above; backends whose pipeline produces both at once override it.
+    fn recognize_with_segments(&self, img: &DynamicImage) -> Result<(String, Vec<OcrSegment>)> {
+        Ok((self.recognize(img)?, self.segments(img)?))
+    }
+
+    /// The identifier recorded in the `ocr_engine` column.
+    fn name(&self) -> &'static str;
+
+    /// The language set recorded in the `ocr_lang` column. Engines without a
+    /// notion of language report an empty string.
+    fn language(&self) -> &str {
+        ""
+    }
+
+    /// Fail fast if the engine cannot be initialized (eg missing traineddata),
+    /// before any images are processed.
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The embedded `ocrs`/`rten` engine.
+pub struct OcrsBackend;
+
+impl OcrBackend for OcrsBackend {
+    fn recognize(&self, img: &DynamicImage) -> Result<String> {
+        extract_text(img)
+    }
+
+    fn segments(&self, img: &DynamicImage) -> Result<Vec<OcrSegment>> {
+        extract_segments(img)
+    }
+
+    fn recognize_with_segments(&self, img: &DynamicImage) -> Result<(String, Vec<OcrSegment>)> {
+        // Run the detect+recognize pipeline once and derive the flat text from
+        // the segments rather than OCRing the image a second time.
+        let segments = extract_segments(img)?;
+        let text = segments
+            .iter()
+            .map(|seg| seg.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok((text, segments))
+    }
+
+    fn name(&self) -> &'static str {
+        "ocrs"
+    }
+}
+
+thread_local! {
+    // One Tesseract engine per rayon worker. LepTess is neither Send nor Sync,
+    // so it cannot live on the backend value; keeping it thread-local avoids
+    // re-initializing the engine for every image.
+    static TESS: RefCell<Option<LepTess>> = const { RefCell::new(None) };
+}
+
+/// The Tesseract/`leptess` engine.
+pub struct TesseractBackend {
+    /// One or more `+`-joined traineddata languages, eg `eng+deu+jpn`.
+    lang: String,
+    /// Tesseract page-segmentation mode passed to `TesseditPagesegMode`.
+    psm: u8,
+    debug: bool,
+}
+
+impl TesseractBackend {
+    pub fn new(lang: String, psm: u8, debug: bool) -> Self {
+        Self { lang, psm, debug }
+    }
+
+    /// Initialize a Tesseract engine configured from this backend's settings.
+    fn init_engine(&self) -> Result<LepTess> {
+        let mut ocr = LepTess::new(None, &self.lang).with_context(|| {
+            format!(
+                "Failed to initialize Tesseract for language set '{}'; ensure the \
+                 matching .traineddata files are installed",
+                self.lang
+            )
+        })?;
+        ocr.set_variable(Variable::TesseditPagesegMode, &self.psm.to_string())
+            .context("Failed to set TesseditPagesegMode")?;
+        if !self.debug {
+            ocr.set_variable(Variable::DebugFile, "/dev/null")
+                .context("Failed to set DebugFile")?;
+        }
+        Ok(ocr)
+    }
+}
+
+impl OcrBackend for TesseractBackend {
+    fn recognize(&self, img: &DynamicImage) -> Result<String> {
+        // Tesseract reads from a file, so stage the frame as a temporary PNG.
+        let temp_file = Builder::new()
+            .suffix(".png")
+            .tempfile()
+            .context("Failed to create temporary file")?;
+        img.save_with_format(temp_file.path(), ImageFormat::Png)
+            .context("Failed to save image to temporary file")?;
+
+        TESS.with(|cell| {
+            let mut slot = cell.borrow_mut();
+            if slot.is_none() {
+                *slot = Some(self.init_engine()?);
+            }
+            let ocr = slot.as_mut().expect("engine initialized above");
+            ocr.set_image(temp_file.path())
+                .context("Failed to set image for OCR")?;
+            ocr.get_utf8_text().context("Failed to get OCR text")
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "tesseract"
+    }
+
+    fn language(&self) -> &str {
+        &self.lang
+    }
+
+    fn validate(&self) -> Result<()> {
+        // Build a throwaway engine so a missing traineddata file surfaces as a
+        // clear error up front rather than on the first worker thread.
+        self.init_engine().map(|_| ())
+    }
+}
+
+/// Perform OCR on an image and return each line's text with its bounding box.
+pub fn extract_segments(image: &DynamicImage) -> Result<Vec<OcrSegment>> {
     // Convert DynamicImage to RGB8 format expected by ocrs
     let rgb_image = image.to_rgb8();
     let img_source = ImageSource::from_bytes(rgb_image.as_raw(), rgb_image.dimensions())
@@ -52,11 +214,52 @@ pub fn extract_text(image: &DynamicImage) -> Result<String> {
         .recognize_text(&ocr_input, &line_rects)
         .context("Failed to recognize text")?;
 
-    let text: String = line_texts
+    // Pair each recognized line with the bounding box of the word rects it was
+    // detected from, so the geometry survives instead of being flattened away.
+    let segments = line_texts
         .iter()
-        .filter_map(|line| line.as_ref().map(|l| l.to_string()))
+        .zip(line_rects.iter())
+        .filter_map(|(line, words)| {
+            let line = line.as_ref()?;
+            let bbox = line_bbox(words)?;
+            Some(OcrSegment {
+                text: line.to_string(),
+                bbox,
+            })
+        })
+        .collect();
+
+    Ok(segments)
+}
+
+/// Perform OCR on an image and return the extracted text.
+pub fn extract_text(image: &DynamicImage) -> Result<String> {
+    let text = extract_segments(image)?
+        .iter()
+        .map(|seg| seg.text.as_str())
         .collect::<Vec<_>>()
         .join("\n");
-
     Ok(text)
 }
+
+/// Union the bounding rects of a line's word boxes into a single pixel-space box.
+fn line_bbox<R: BoundingRect>(words: &[R]) -> Option<BBox> {
+    let mut bounds: Option<(f32, f32, f32, f32)> = None;
+    for word in words {
+        let rect = word.bounding_rect();
+        let (left, top, right, bottom) =
+            (rect.left(), rect.top(), rect.right(), rect.bottom());
+        bounds = Some(match bounds {
+            Some((l, t, r, b)) => (l.min(left), t.min(top), r.max(right), b.max(bottom)),
+            None => (left, top, right, bottom),
+        });
+    }
+
+    let (l, t, r, b) = bounds?;
+    Some(BBox {
+        x: l.round() as i32,
+        y: t.round() as i32,
+        w: (r - l).round() as i32,
+        h: (b - t).round() as i32,
+    })
+}